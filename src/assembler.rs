@@ -1,18 +1,111 @@
 use std::collections::HashMap;
 use std::fs::File;
 use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
 
-pub fn assemble(asm_file_path: &str, binary_file_path: &str) {
-    let command_table = *parse_file(asm_file_path);
-    let mut symbol_table = *gen_symbol_table(&command_table);
-    parse_command_table(&command_table, &mut symbol_table, binary_file_path);
+use crate::error::{AsmError, AsmErrorKind};
+use crate::preprocessor::expand_macros;
+
+/// The 16-bit word encoding used by the `.hack` format, plus two encodings
+/// aimed at emulators/ROM tooling that don't expect the nand2tetris
+/// ASCII-bitstring form.
+#[derive(Clone, Copy, Debug)]
+pub enum OutputFormat {
+    /// The current `.hack` form: one 16-char line of `0`/`1` per word.
+    Ascii,
+    /// Packed little-endian binary, two bytes per word, no newlines.
+    Binary,
+    /// One `%04X` hex line per word.
+    Hex,
+}
+
+fn render_word(word: i16, format: OutputFormat) -> Vec<u8> {
+    match format {
+        OutputFormat::Ascii => format!("{:016b}\n", word as u16).into_bytes(),
+        OutputFormat::Binary => (word as u16).to_le_bytes().to_vec(),
+        OutputFormat::Hex => format!("{:04X}\n", word as u16).into_bytes(),
+    }
+}
+
+pub fn assemble(
+    asm_file_path: &str,
+    output_file_path: &str,
+    format: OutputFormat,
+    emit_symbol_map: bool,
+) -> Result<(), Vec<AsmError>> {
+    let command_table = match parse_file(asm_file_path) {
+        Ok(command_table) => *command_table,
+        Err(errors) => return report(errors),
+    };
+    // Keep going through codegen even if the symbol table has errors (e.g. a
+    // duplicate label), so unrelated codegen errors elsewhere in the file are
+    // still surfaced on this run instead of being hidden behind the first
+    // pass's failure.
+    let (mut symbol_table, mut errors) = gen_symbol_table(&command_table);
+    let words = match encode_commands(&command_table, &mut symbol_table) {
+        Ok(words) => Some(words),
+        Err(command_errors) => {
+            errors.extend(command_errors);
+            None
+        }
+    };
+    if !errors.is_empty() {
+        return report(errors);
+    }
+    write_output(&words.unwrap(), output_file_path, format);
+    if emit_symbol_map {
+        write_symbol_map(&symbol_table, output_file_path);
+    }
+    Ok(())
+}
+
+fn report(errors: Vec<AsmError>) -> Result<(), Vec<AsmError>> {
+    for error in &errors {
+        error.print();
+    }
+    Err(errors)
+}
+
+enum SymbolKind {
+    Predefined,
+    Label,
+    Variable,
 }
 
 struct SymbolTable {
     map: HashMap<String, i16>,
+    kinds: HashMap<String, SymbolKind>,
     next_ram_idx: i16,
 }
 
+fn insert_symbol(symbol_table: &mut SymbolTable, symbol: String, address: i16, kind: SymbolKind) {
+    symbol_table.kinds.insert(symbol.clone(), kind);
+    symbol_table.map.insert(symbol, address);
+}
+
+/// Writes a `symbols.txt` sidecar next to `output_file_path` listing every
+/// resolved symbol, its kind, and its numeric value, sorted by address and
+/// then by name. The name is needed as a tiebreaker (several predefined
+/// symbols alias the same address, e.g. `SP`/`R0`) since `symbol_table.map`
+/// is a `HashMap` and its iteration order is otherwise randomized per
+/// process, which would make the sidecar non-reproducible.
+fn write_symbol_map(symbol_table: &SymbolTable, output_file_path: &str) {
+    let directory = Path::new(output_file_path).parent().unwrap_or_else(|| Path::new("."));
+    let symbol_map_path = directory.join("symbols.txt");
+    let mut entries: Vec<(&String, &i16)> = symbol_table.map.iter().collect();
+    entries.sort_by(|(a_symbol, a_address), (b_symbol, b_address)| a_address.cmp(b_address).then(a_symbol.cmp(b_symbol)));
+    let mut symbol_map_file = File::create(symbol_map_path).expect("create symbol map file fail");
+    for (symbol, address) in entries {
+        let kind = match symbol_table.kinds.get(symbol).expect("symbol missing kind") {
+            SymbolKind::Predefined => "predefined",
+            SymbolKind::Label => "label",
+            SymbolKind::Variable => "variable",
+        };
+        let line = format!("{symbol}\t{kind}\t{address}\n");
+        symbol_map_file.write_all(line.as_bytes()).expect("write file fail");
+    }
+}
+
 enum CommandType {
     A,
     C,
@@ -20,8 +113,8 @@ enum CommandType {
 }
 
 struct Command {
-    #[allow(dead_code)]
     line_orig: String,
+    line_number: usize,
     command_type: CommandType,
     rom_idx: i16,
     symbol: Option<String>, // A, L
@@ -30,55 +123,76 @@ struct Command {
     dest: Option<String>,   // C
 }
 
-fn gen_symbol_table(command_table: &Vec<Command>) -> Box<SymbolTable> {
-    let mut symbol_table = SymbolTable { map: HashMap::new(), next_ram_idx: 16 };
+/// Builds the symbol table, collecting a `DuplicateLabel` error for any
+/// repeated `(LABEL)` without aborting: the table still gets a usable (first
+/// occurrence wins) entry for every label, so a later codegen pass can run
+/// and report its own, independent errors in the same pass.
+fn gen_symbol_table(command_table: &Vec<Command>) -> (SymbolTable, Vec<AsmError>) {
+    let mut symbol_table = SymbolTable { map: HashMap::new(), kinds: HashMap::new(), next_ram_idx: 16 };
     add_default_symbols(&mut symbol_table);
+    let mut errors = Vec::new();
     for command in command_table {
-        match command.command_type {
-            CommandType::L => {
-                symbol_table.map.insert(command.symbol.as_ref().unwrap().clone(), command.rom_idx);
+        if let CommandType::L = command.command_type {
+            let label = command.symbol.as_ref().unwrap().clone();
+            if matches!(symbol_table.kinds.get(&label), Some(SymbolKind::Label)) {
+                errors.push(AsmError::new(command.line_number, &command.line_orig, AsmErrorKind::DuplicateLabel(label)));
+                continue;
             }
-            _ => {}
-        };
+            insert_symbol(&mut symbol_table, label, command.rom_idx, SymbolKind::Label);
+        }
     }
-    Box::new(symbol_table)
+    (symbol_table, errors)
 }
 
 fn add_default_symbols(symbol_table: &mut SymbolTable) {
-    symbol_table.map.insert("SP".to_string(), 0);
-    symbol_table.map.insert("LCL".to_string(), 1);
-    symbol_table.map.insert("ARG".to_string(), 2);
-    symbol_table.map.insert("THIS".to_string(), 3);
-    symbol_table.map.insert("THAT".to_string(), 4);
-    symbol_table.map.insert("SCREEN".to_string(), 16384);
-    symbol_table.map.insert("KBD".to_string(), 24576);
+    insert_symbol(symbol_table, "SP".to_string(), 0, SymbolKind::Predefined);
+    insert_symbol(symbol_table, "LCL".to_string(), 1, SymbolKind::Predefined);
+    insert_symbol(symbol_table, "ARG".to_string(), 2, SymbolKind::Predefined);
+    insert_symbol(symbol_table, "THIS".to_string(), 3, SymbolKind::Predefined);
+    insert_symbol(symbol_table, "THAT".to_string(), 4, SymbolKind::Predefined);
+    insert_symbol(symbol_table, "SCREEN".to_string(), 16384, SymbolKind::Predefined);
+    insert_symbol(symbol_table, "KBD".to_string(), 24576, SymbolKind::Predefined);
     for i in 0..16 {
         let symbol = format!("R{i}");
-        symbol_table.map.insert(symbol, i);
+        insert_symbol(symbol_table, symbol, i, SymbolKind::Predefined);
     }
 }
 
-fn parse_file(file_path: &str) -> Box<Vec<Command>> {
+fn parse_file(file_path: &str) -> Result<Box<Vec<Command>>, Vec<AsmError>> {
     let file = File::open(file_path).expect("open file fail");
     let reader = BufReader::new(file);
-    let mut rom_idx = 0;
-    let mut command_table = Vec::new();
+    let mut raw_lines = Vec::new();
     for (idx, line) in reader.lines().enumerate() {
         let line = line.expect(&format!("line {} parse fail", idx));
-        let line = line.split("//").next().unwrap().trim();
+        let line = line.split("//").next().unwrap().trim().to_string();
         if line.is_empty() {
             continue;
         }
-        command_table.push(*parse_line(line, &mut rom_idx));
+        raw_lines.push((idx + 1, line));
+    }
+    let expanded_lines = expand_macros(raw_lines)?;
+    let mut rom_idx = 0;
+    let mut command_table = Vec::new();
+    let mut errors = Vec::new();
+    for (line_number, line) in &expanded_lines {
+        match parse_line(line, &mut rom_idx, *line_number) {
+            Ok(command) => command_table.push(*command),
+            Err(error) => errors.push(error),
+        }
+    }
+    if errors.is_empty() {
+        Ok(Box::new(command_table))
+    } else {
+        Err(errors)
     }
-    Box::new(command_table)
 }
 
-fn parse_line(line: &str, rom_idx: &mut i16) -> Box<Command> {
+fn parse_line(line: &str, rom_idx: &mut i16, line_number: usize) -> Result<Box<Command>, AsmError> {
     if line.starts_with("@") {
         let symbol = Some(line[1..].to_string());
         let command = Box::new(Command {
             line_orig: String::from(line),
+            line_number,
             command_type: CommandType::A,
             rom_idx: *rom_idx,
             symbol,
@@ -87,24 +201,28 @@ fn parse_line(line: &str, rom_idx: &mut i16) -> Box<Command> {
             dest: None,
         });
         *rom_idx += 1;
-        command
-    } else if line.starts_with("(") && line.ends_with(")") {
+        Ok(command)
+    } else if line.starts_with("(") {
+        if !line.ends_with(")") {
+            return Err(AsmError::new(line_number, line, AsmErrorKind::UnterminatedLabel));
+        }
         let symbol = Some(line[1..line.len() - 1].to_string());
-        Box::new(Command {
+        Ok(Box::new(Command {
             line_orig: line.to_string(),
+            line_number,
             command_type: CommandType::L,
             rom_idx: *rom_idx,
             symbol,
             comp: None,
             jump: None,
             dest: None,
-        })
+        }))
     } else {
-        parse_c_line(line, rom_idx)
+        parse_c_line(line, rom_idx, line_number)
     }
 }
 
-fn parse_c_line(line: &str, rom_idx: &mut i16) -> Box<Command> {
+fn parse_c_line(line: &str, rom_idx: &mut i16, line_number: usize) -> Result<Box<Command>, AsmError> {
     let split_jump: Vec<&str> = line.split(';').collect();
     let jump = if split_jump.len() > 1 {
         Some(split_jump[1].trim().to_string())
@@ -122,6 +240,7 @@ fn parse_c_line(line: &str, rom_idx: &mut i16) -> Box<Command> {
     };
     let command = Box::new(Command {
         line_orig: line.to_string(),
+        line_number,
         command_type: (CommandType::C),
         rom_idx: *rom_idx,
         symbol: None,
@@ -130,133 +249,276 @@ fn parse_c_line(line: &str, rom_idx: &mut i16) -> Box<Command> {
         dest,
     });
     *rom_idx += 1;
-    command
+    Ok(command)
 }
 
-fn parse_command_table(
-    command_table: &Vec<Command>,
-    symbol_table: &mut SymbolTable,
-    binary_file_path: &str,
-) {
-    let mut binary_file = File::create(binary_file_path).expect("create binary file fail");
+/// Encodes every command to its 16-bit word, collecting all codegen errors
+/// instead of stopping at the first one. Address resolution for an A-command
+/// that names a duplicated label may be wrong (it resolves against whichever
+/// occurrence `gen_symbol_table` kept), but that's already reported as its
+/// own `DuplicateLabel` error, so running this pass regardless still gets the
+/// user every other, independent codegen error in one run.
+fn encode_commands(command_table: &Vec<Command>, symbol_table: &mut SymbolTable) -> Result<Vec<i16>, Vec<AsmError>> {
+    let mut words = Vec::new();
+    let mut errors = Vec::new();
     for command in command_table {
-        match command.command_type {
-            CommandType::A => parse_a_command(command, &mut binary_file, symbol_table),
-            CommandType::C => parse_c_command(command, &mut binary_file),
-            _ => {},
+        let encoded = match command.command_type {
+            CommandType::A => parse_a_command(command, symbol_table),
+            CommandType::C => parse_c_command(command),
+            CommandType::L => continue,
         };
+        match encoded {
+            Ok(word) => words.push(word),
+            Err(error) => errors.push(error),
+        }
+    }
+    if errors.is_empty() {
+        Ok(words)
+    } else {
+        Err(errors)
     }
 }
 
-fn parse_a_command(command: &Command, binary_file: &mut File, symbol_table: &mut SymbolTable) {
+fn write_output(words: &[i16], output_file_path: &str, format: OutputFormat) {
+    let mut output_file = File::create(output_file_path).expect("create output file fail");
+    for &word in words {
+        output_file.write_all(&render_word(word, format)).expect("write file fail");
+    }
+}
+
+fn parse_a_command(command: &Command, symbol_table: &mut SymbolTable) -> Result<i16, AsmError> {
     let symbol = command
         .symbol
         .as_ref()
         .expect("A-type command should have symbol");
-    let if_number = symbol.parse::<i16>();
-    let address;
-    match if_number {
-        Ok(number) => {
-            address = number;
-        },
-        Err(_) => {
-            address = handle_a_symbol(symbol, symbol_table);
+    if symbol.chars().all(|c| c.is_ascii_digit()) {
+        let value: i64 = symbol.parse().map_err(|_| {
+            AsmError::new(command.line_number, &command.line_orig, AsmErrorKind::MalformedAInstruction(symbol.clone()))
+        })?;
+        if value > 32767 {
+            return Err(AsmError::new(command.line_number, &command.line_orig, AsmErrorKind::ConstantOutOfRange(value)));
         }
+        Ok(value as i16)
+    } else {
+        Ok(handle_a_symbol(symbol, symbol_table))
     }
-    let binary_str = format!("{:0>16b}\n", address);
-    binary_file.write_all(binary_str.as_bytes()).expect("write file fail");
 }
 
 fn handle_a_symbol(symbol: &String, symbol_table: &mut SymbolTable) -> i16 {
     if symbol_table.map.contains_key(symbol) {
         return *symbol_table.map.get(symbol).unwrap();
     }
-    symbol_table.map.insert(symbol.clone(), symbol_table.next_ram_idx);
+    let ram_idx = symbol_table.next_ram_idx;
+    insert_symbol(symbol_table, symbol.clone(), ram_idx, SymbolKind::Variable);
     symbol_table.next_ram_idx += 1;
-    return symbol_table.next_ram_idx - 1;
+    return ram_idx;
 }
 
-fn parse_c_command(command: &Command, binary_file: &mut File) {
-    let comp = parse_comp(command);
-    let dest = parse_dest(command);
-    let jump = parse_jump(command);
-    let binary_str = format!("111{comp}{dest}{jump}\n");
-    binary_file
-        .write_all(binary_str.as_bytes())
-        .expect("write file fail");
+fn parse_c_command(command: &Command) -> Result<i16, AsmError> {
+    let comp = parse_comp(command)?;
+    let dest = parse_dest(command)?;
+    let jump = parse_jump(command)?;
+    let bits = format!("111{comp}{dest}{jump}");
+    Ok(u16::from_str_radix(&bits, 2).expect("internal: invalid C-instruction bit pattern") as i16)
 }
 
-fn parse_jump(command: &Command) -> &str {
-    let jump = command.jump.as_ref();
-    if jump.is_none() {
-        return "000";
-    }
-    let jump = jump.unwrap().as_str();
-    match jump {
-        "JGT" => "001",
-        "JEQ" => "010",
-        "JGE" => "011",
-        "JLT" => "100",
-        "JNE" => "101",
-        "JLE" => "110",
-        "JMP" => "111",
-        _ => unreachable!(),
-    }
+/// Mnemonic/bits pairs for the `jump` field, shared with the disassembler
+/// which inverts this table into a bits→mnemonic lookup.
+pub(crate) const JUMP_TABLE: &[(&str, &str)] = &[
+    ("JGT", "001"),
+    ("JEQ", "010"),
+    ("JGE", "011"),
+    ("JLT", "100"),
+    ("JNE", "101"),
+    ("JLE", "110"),
+    ("JMP", "111"),
+];
+
+/// Mnemonic/bits pairs for the `dest` field, shared with the disassembler
+/// which inverts this table into a bits→mnemonic lookup.
+pub(crate) const DEST_TABLE: &[(&str, &str)] = &[
+    ("M", "001"),
+    ("D", "010"),
+    ("MD", "011"),
+    ("A", "100"),
+    ("AM", "101"),
+    ("AD", "110"),
+    ("AMD", "111"),
+];
+
+/// Mnemonic/bits pairs for the `comp` field, shared with the disassembler
+/// which inverts this table into a bits→mnemonic lookup.
+pub(crate) const COMP_TABLE: &[(&str, &str)] = &[
+    ("0", "0101010"),
+    ("1", "0111111"),
+    ("-1", "0111010"),
+    ("D", "0001100"),
+    ("A", "0110000"),
+    ("!D", "0001101"),
+    ("!A", "0110001"),
+    ("-D", "0001111"),
+    ("-A", "0110011"),
+    ("D+1", "0011111"),
+    ("A+1", "0110111"),
+    ("D-1", "0001110"),
+    ("A-1", "0110010"),
+    ("D+A", "0000010"),
+    ("D-A", "0010011"),
+    ("A-D", "0000111"),
+    ("D&A", "0000000"),
+    ("D|A", "0010101"),
+    ("M", "1110000"),
+    ("!M", "1110001"),
+    ("-M", "1110011"),
+    ("M+1", "1110111"),
+    ("M-1", "1110010"),
+    ("D+M", "1000010"),
+    ("D-M", "1010011"),
+    ("M-D", "1000111"),
+    ("D&M", "1000000"),
+    ("D|M", "1010101"),
+];
+
+fn parse_jump(command: &Command) -> Result<&'static str, AsmError> {
+    let jump = match command.jump.as_ref() {
+        None => return Ok("000"),
+        Some(jump) => jump.as_str(),
+    };
+    JUMP_TABLE
+        .iter()
+        .find(|(mnemonic, _)| *mnemonic == jump)
+        .map(|(_, bits)| *bits)
+        .ok_or_else(|| AsmError::new(command.line_number, &command.line_orig, AsmErrorKind::UnknownJump(jump.to_string())))
 }
 
-fn parse_dest(command: &Command) -> &str {
-    let dest = command.dest.as_ref();
-    if dest.is_none() {
-        return "000";
-    }
-    let dest = dest.unwrap().as_str();
-    match dest {
-        "M" => "001",
-        "D" => "010",
-        "MD" => "011",
-        "A" => "100",
-        "AM" => "101",
-        "AD" => "110",
-        "AMD" => "111",
-        _ => unreachable!(),
-    }
+fn parse_dest(command: &Command) -> Result<&'static str, AsmError> {
+    let dest = match command.dest.as_ref() {
+        None => return Ok("000"),
+        Some(dest) => dest.as_str(),
+    };
+    DEST_TABLE
+        .iter()
+        .find(|(mnemonic, _)| *mnemonic == dest)
+        .map(|(_, bits)| *bits)
+        .ok_or_else(|| AsmError::new(command.line_number, &command.line_orig, AsmErrorKind::UnknownDest(dest.to_string())))
 }
 
-fn parse_comp(command: &Command) -> &str {
+fn parse_comp(command: &Command) -> Result<&'static str, AsmError> {
     let comp_str = command
         .comp
         .as_ref()
         .expect("C-type command should have comp")
         .as_str();
-    match comp_str {
-        "0" => "0101010",
-        "1" => "0111111",
-        "-1" => "0111010",
-        "D" => "0001100",
-        "A" => "0110000",
-        "!D" => "0001101",
-        "!A" => "0110001",
-        "-D" => "0001111",
-        "-A" => "0110011",
-        "D+1" => "0011111",
-        "A+1" => "0110111",
-        "D-1" => "0001110",
-        "A-1" => "0110010",
-        "D+A" => "0000010",
-        "D-A" => "0010011",
-        "A-D" => "0000111",
-        "D&A" => "0000000",
-        "D|A" => "0010101",
-        "M" => "1110000",
-        "!M" => "1110001",
-        "-M" => "1110011",
-        "M+1" => "1110111",
-        "M-1" => "1110010",
-        "D+M" => "1000010",
-        "D-M" => "1010011",
-        "M-D" => "1000111",
-        "D&M" => "1000000",
-        "D|M" => "1010101",
-        _ => unreachable!(),
+    COMP_TABLE
+        .iter()
+        .find(|(mnemonic, _)| *mnemonic == comp_str)
+        .map(|(_, bits)| *bits)
+        .ok_or_else(|| AsmError::new(command.line_number, &command.line_orig, AsmErrorKind::UnknownComp(comp_str.to_string())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn label(name: &str, line_number: usize, rom_idx: i16) -> Command {
+        Command {
+            line_orig: format!("({name})"),
+            line_number,
+            command_type: CommandType::L,
+            rom_idx,
+            symbol: Some(name.to_string()),
+            comp: None,
+            jump: None,
+            dest: None,
+        }
+    }
+
+    fn a_command(symbol: &str, line_number: usize, rom_idx: i16) -> Command {
+        Command {
+            line_orig: format!("@{symbol}"),
+            line_number,
+            command_type: CommandType::A,
+            rom_idx,
+            symbol: Some(symbol.to_string()),
+            comp: None,
+            jump: None,
+            dest: None,
+        }
+    }
+
+    fn c_command(comp: &str, line_number: usize, rom_idx: i16) -> Command {
+        Command {
+            line_orig: comp.to_string(),
+            line_number,
+            command_type: CommandType::C,
+            rom_idx,
+            symbol: None,
+            comp: Some(comp.to_string()),
+            jump: None,
+            dest: None,
+        }
+    }
+
+    #[test]
+    fn gen_symbol_table_reports_duplicate_label_but_keeps_first_occurrence() {
+        let command_table = vec![label("LOOP", 1, 0), label("LOOP", 3, 1)];
+        let (symbol_table, errors) = gen_symbol_table(&command_table);
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(errors[0].kind, AsmErrorKind::DuplicateLabel(_)));
+        assert_eq!(symbol_table.map.get("LOOP"), Some(&0));
+    }
+
+    #[test]
+    fn encode_commands_runs_despite_duplicate_label_error() {
+        // A duplicate label (reported by gen_symbol_table) must not hide an
+        // unrelated, independent bad-mnemonic error from encode_commands.
+        let command_table = vec![label("LOOP", 1, 0), label("LOOP", 2, 1), c_command("D+Z", 3, 1)];
+        let (mut symbol_table, symbol_errors) = gen_symbol_table(&command_table);
+        assert_eq!(symbol_errors.len(), 1);
+        let command_errors = encode_commands(&command_table, &mut symbol_table).expect_err("bad comp should error");
+        assert_eq!(command_errors.len(), 1);
+        assert!(matches!(command_errors[0].kind, AsmErrorKind::UnknownComp(_)));
+    }
+
+    #[test]
+    fn encode_commands_allocates_variables_in_ram_order() {
+        let command_table = vec![a_command("foo", 1, 0), a_command("bar", 2, 1)];
+        let (mut symbol_table, errors) = gen_symbol_table(&command_table);
+        assert!(errors.is_empty());
+        let words = encode_commands(&command_table, &mut symbol_table).expect("should encode");
+        assert_eq!(words, vec![16, 17]);
+    }
+
+    #[test]
+    fn render_word_matches_expected_bytes_per_format() {
+        let word = -1i16; // all 16 bits set
+        assert_eq!(render_word(word, OutputFormat::Ascii), b"1111111111111111\n".to_vec());
+        assert_eq!(render_word(word, OutputFormat::Binary), vec![0xFF, 0xFF]);
+        assert_eq!(render_word(word, OutputFormat::Hex), b"FFFF\n".to_vec());
+    }
+
+    #[test]
+    fn write_symbol_map_orders_deterministically_by_address_then_name() {
+        let mut symbol_table = SymbolTable { map: HashMap::new(), kinds: HashMap::new(), next_ram_idx: 16 };
+        add_default_symbols(&mut symbol_table);
+        insert_symbol(&mut symbol_table, "LOOP".to_string(), 5, SymbolKind::Label);
+        insert_symbol(&mut symbol_table, "counter".to_string(), 16, SymbolKind::Variable);
+
+        let dir = std::env::temp_dir().join("rust_assembler_write_symbol_map_test");
+        std::fs::create_dir_all(&dir).unwrap();
+        let output_path = dir.join("out.hack");
+        write_symbol_map(&symbol_table, output_path.to_str().unwrap());
+
+        // SP and R0 both resolve to address 0: without a name tiebreaker
+        // their relative order would depend on HashMap iteration, which is
+        // randomized per process.
+        let contents = std::fs::read_to_string(dir.join("symbols.txt")).unwrap();
+        let lines: Vec<&str> = contents.lines().collect();
+        assert_eq!(lines[0], "R0\tpredefined\t0");
+        assert_eq!(lines[1], "SP\tpredefined\t0");
+        assert!(lines.contains(&"LOOP\tlabel\t5"));
+        assert!(lines.contains(&"counter\tvariable\t16"));
+
+        std::fs::remove_dir_all(&dir).unwrap();
     }
 }
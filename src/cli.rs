@@ -0,0 +1,154 @@
+use std::env;
+use std::process;
+
+use crate::assembler::{self, OutputFormat};
+use crate::disassembler;
+
+/// Hand-rolled `assemble`/`disassemble` front end: `<subcommand> <input>
+/// <output> [--format ascii|binary|hex] [--no-symbol-map]`. `--format`
+/// applies to both subcommands (disassemble reads back whichever encoding
+/// assemble wrote). `--no-symbol-map` also applies to both: for assemble it
+/// skips writing the `symbols.txt` sidecar; for disassemble it skips reading
+/// one back in, so addresses are left as `@N` instead of `@SYMBOL`.
+pub fn run() {
+    let mut args = env::args().skip(1);
+    let subcommand = args.next().unwrap_or_else(|| usage_error("missing subcommand"));
+    match subcommand.as_str() {
+        "assemble" => run_assemble(args),
+        "disassemble" => run_disassemble(args),
+        "-h" | "--help" => print_usage(),
+        other => usage_error(&format!("unknown subcommand '{other}'")),
+    }
+}
+
+fn run_assemble(args: impl Iterator<Item = String>) {
+    let (input_path, output_path, format, use_symbol_map) = parse_positionals_and_format(args);
+    match assembler::assemble(&input_path, &output_path, format, use_symbol_map) {
+        Ok(()) => println!("wrote {output_path}"),
+        Err(errors) => {
+            eprintln!("assembly failed with {} error(s)", errors.len());
+            process::exit(1);
+        }
+    }
+}
+
+fn run_disassemble(args: impl Iterator<Item = String>) {
+    let (input_path, output_path, format, use_symbol_map) = parse_positionals_and_format(args);
+    disassembler::disassemble(&input_path, &output_path, format, use_symbol_map);
+    println!("wrote {output_path}");
+}
+
+fn parse_positionals_and_format(args: impl Iterator<Item = String>) -> (String, String, OutputFormat, bool) {
+    match try_parse_positionals_and_format(args) {
+        Ok(parsed) => parsed,
+        Err(message) => usage_error(&message),
+    }
+}
+
+/// Pure parsing logic behind `parse_positionals_and_format`, split out so
+/// tests can exercise error cases (bad flag values, wrong positional count)
+/// without going through `usage_error`'s `process::exit`.
+fn try_parse_positionals_and_format(
+    args: impl Iterator<Item = String>,
+) -> Result<(String, String, OutputFormat, bool), String> {
+    let mut positionals = Vec::new();
+    let mut format = OutputFormat::Ascii;
+    let mut use_symbol_map = true;
+    let mut args = args.peekable();
+    while let Some(arg) = args.next() {
+        if arg == "--format" {
+            let value = args.next().ok_or("--format requires a value")?;
+            format = parse_format(&value)?;
+        } else if let Some(value) = arg.strip_prefix("--format=") {
+            format = parse_format(value)?;
+        } else if arg == "--no-symbol-map" {
+            use_symbol_map = false;
+        } else {
+            positionals.push(arg);
+        }
+    }
+    if positionals.len() != 2 {
+        return Err("expected <input> <output> positional arguments".to_string());
+    }
+    let output_path = positionals.pop().unwrap();
+    let input_path = positionals.pop().unwrap();
+    Ok((input_path, output_path, format, use_symbol_map))
+}
+
+fn parse_format(value: &str) -> Result<OutputFormat, String> {
+    match value {
+        "ascii" => Ok(OutputFormat::Ascii),
+        "binary" => Ok(OutputFormat::Binary),
+        "hex" => Ok(OutputFormat::Hex),
+        other => Err(format!("unknown format '{other}', expected ascii|binary|hex")),
+    }
+}
+
+fn print_usage() {
+    println!(
+        "usage: rust_assembler <assemble|disassemble> <input> <output> [--format ascii|binary|hex] [--no-symbol-map]"
+    );
+}
+
+fn usage_error(message: &str) -> ! {
+    eprintln!("error: {message}");
+    print_usage();
+    process::exit(1);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(raw: &[&str]) -> impl Iterator<Item = String> {
+        raw.iter().map(|s| s.to_string()).collect::<Vec<_>>().into_iter()
+    }
+
+    #[test]
+    fn defaults_to_ascii_format_and_symbol_map_on() {
+        let (input_path, output_path, format, use_symbol_map) =
+            try_parse_positionals_and_format(args(&["in.asm", "out.hack"])).expect("should parse");
+        assert_eq!(input_path, "in.asm");
+        assert_eq!(output_path, "out.hack");
+        assert!(matches!(format, OutputFormat::Ascii));
+        assert!(use_symbol_map);
+    }
+
+    #[test]
+    fn parses_format_flag_as_separate_and_equals_form() {
+        let (_, _, format, _) =
+            try_parse_positionals_and_format(args(&["in.asm", "out.hack", "--format", "binary"])).expect("should parse");
+        assert!(matches!(format, OutputFormat::Binary));
+
+        let (_, _, format, _) =
+            try_parse_positionals_and_format(args(&["in.asm", "out.hack", "--format=hex"])).expect("should parse");
+        assert!(matches!(format, OutputFormat::Hex));
+    }
+
+    #[test]
+    fn rejects_unknown_format_value() {
+        let error = try_parse_positionals_and_format(args(&["in.asm", "out.hack", "--format", "nonsense"]))
+            .expect_err("unknown format should error");
+        assert!(error.contains("unknown format"));
+    }
+
+    #[test]
+    fn parses_no_symbol_map_flag() {
+        let (_, _, _, use_symbol_map) =
+            try_parse_positionals_and_format(args(&["in.asm", "out.hack", "--no-symbol-map"])).expect("should parse");
+        assert!(!use_symbol_map);
+    }
+
+    #[test]
+    fn rejects_missing_positional() {
+        let error = try_parse_positionals_and_format(args(&["in.asm"])).expect_err("missing output should error");
+        assert!(error.contains("positional"));
+    }
+
+    #[test]
+    fn rejects_extra_positional() {
+        let error =
+            try_parse_positionals_and_format(args(&["in.asm", "out.hack", "extra"])).expect_err("extra arg should error");
+        assert!(error.contains("positional"));
+    }
+}
@@ -0,0 +1,211 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::Path;
+
+use crate::assembler::{OutputFormat, COMP_TABLE, DEST_TABLE, JUMP_TABLE};
+
+/// Reads a `.hack`-encoded file in the given `OutputFormat` (the assembler's
+/// three output encodings are symmetric: whatever `assemble --format X`
+/// wrote, `disassemble --format X` can read back) and regenerates Hack
+/// assembly into `asm_file_path`, inverting the mnemonic/bits tables the
+/// assembler uses to encode commands. If `use_symbol_map` is set and a
+/// `symbols.txt` sidecar (see the symbol-map feature) sits next to
+/// `hack_file_path`, known addresses are re-substituted back into named
+/// `@SYMBOL` form.
+pub fn disassemble(hack_file_path: &str, asm_file_path: &str, format: OutputFormat, use_symbol_map: bool) {
+    let comp_lookup = invert_table(COMP_TABLE);
+    let dest_lookup = invert_table(DEST_TABLE);
+    let jump_lookup = invert_table(JUMP_TABLE);
+    let symbol_map = if use_symbol_map { load_symbol_map(hack_file_path) } else { None };
+
+    let words = read_words(hack_file_path, format);
+    let mut asm_file = File::create(asm_file_path).expect("create asm file fail");
+    for word in words {
+        let asm_line = decode_word(word, &comp_lookup, &dest_lookup, &jump_lookup, &symbol_map);
+        asm_file
+            .write_all(format!("{asm_line}\n").as_bytes())
+            .expect("write file fail");
+    }
+}
+
+/// Reads the raw 16-bit words out of `hack_file_path`, dispatching on
+/// `format` to mirror whichever encoding `render_word` used to write them.
+fn read_words(hack_file_path: &str, format: OutputFormat) -> Vec<u16> {
+    match format {
+        OutputFormat::Ascii => read_ascii_words(hack_file_path),
+        OutputFormat::Binary => read_binary_words(hack_file_path),
+        OutputFormat::Hex => read_hex_words(hack_file_path),
+    }
+}
+
+fn read_ascii_words(hack_file_path: &str) -> Vec<u16> {
+    let file = File::open(hack_file_path).expect("open file fail");
+    let reader = BufReader::new(file);
+    let mut words = Vec::new();
+    for (idx, line) in reader.lines().enumerate() {
+        let line = line.unwrap_or_else(|err| panic!("line {idx} parse fail: {err}"));
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        words.push(u16::from_str_radix(line, 2).expect("malformed binary word"));
+    }
+    words
+}
+
+fn read_binary_words(hack_file_path: &str) -> Vec<u16> {
+    let mut file = File::open(hack_file_path).expect("open file fail");
+    let mut bytes = Vec::new();
+    file.read_to_end(&mut bytes).expect("read file fail");
+    bytes
+        .chunks_exact(2)
+        .map(|chunk| u16::from_le_bytes([chunk[0], chunk[1]]))
+        .collect()
+}
+
+fn read_hex_words(hack_file_path: &str) -> Vec<u16> {
+    let file = File::open(hack_file_path).expect("open file fail");
+    let reader = BufReader::new(file);
+    let mut words = Vec::new();
+    for (idx, line) in reader.lines().enumerate() {
+        let line = line.unwrap_or_else(|err| panic!("line {idx} parse fail: {err}"));
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        words.push(u16::from_str_radix(line, 16).expect("malformed hex word"));
+    }
+    words
+}
+
+fn invert_table(table: &[(&'static str, &'static str)]) -> HashMap<&'static str, &'static str> {
+    table.iter().map(|(mnemonic, bits)| (*bits, *mnemonic)).collect()
+}
+
+fn decode_word(
+    word: u16,
+    comp_lookup: &HashMap<&str, &str>,
+    dest_lookup: &HashMap<&str, &str>,
+    jump_lookup: &HashMap<&str, &str>,
+    symbol_map: &Option<HashMap<i16, String>>,
+) -> String {
+    if word & 0x8000 == 0 {
+        let address = word as i16;
+        match symbol_map.as_ref().and_then(|map| map.get(&address)) {
+            Some(symbol) => format!("@{symbol}"),
+            None => format!("@{address}"),
+        }
+    } else {
+        let bits = format!("{word:016b}");
+        let comp_bits = &bits[3..10];
+        let dest_bits = &bits[10..13];
+        let jump_bits = &bits[13..16];
+        let comp = comp_lookup.get(comp_bits).expect("unknown comp bits");
+        let mut decoded = String::new();
+        if dest_bits != "000" {
+            let dest = dest_lookup.get(dest_bits).expect("unknown dest bits");
+            decoded.push_str(dest);
+            decoded.push('=');
+        }
+        decoded.push_str(comp);
+        if jump_bits != "000" {
+            let jump = jump_lookup.get(jump_bits).expect("unknown jump bits");
+            decoded.push(';');
+            decoded.push_str(jump);
+        }
+        decoded
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_word_round_trips_a_instruction_with_symbol() {
+        let comp_lookup = invert_table(COMP_TABLE);
+        let dest_lookup = invert_table(DEST_TABLE);
+        let jump_lookup = invert_table(JUMP_TABLE);
+        let mut symbol_map = HashMap::new();
+        symbol_map.insert(16, "foo".to_string());
+        let decoded = decode_word(16, &comp_lookup, &dest_lookup, &jump_lookup, &Some(symbol_map));
+        assert_eq!(decoded, "@foo");
+    }
+
+    #[test]
+    fn decode_word_round_trips_c_instruction_with_dest_and_jump() {
+        let comp_lookup = invert_table(COMP_TABLE);
+        let dest_lookup = invert_table(DEST_TABLE);
+        let jump_lookup = invert_table(JUMP_TABLE);
+        // D=D+1;JGT
+        let word = u16::from_str_radix("1110011111010001", 2).unwrap();
+        let decoded = decode_word(word, &comp_lookup, &dest_lookup, &jump_lookup, &None);
+        assert_eq!(decoded, "D=D+1;JGT");
+    }
+
+    #[test]
+    fn read_binary_and_hex_words_agree_with_ascii() {
+        let dir = std::env::temp_dir().join("rust_assembler_disasm_test");
+        std::fs::create_dir_all(&dir).unwrap();
+        let words: Vec<u16> = vec![16, 0b1110_1010_1000_0001u16];
+
+        let ascii_path = dir.join("words.hack");
+        std::fs::write(&ascii_path, words.iter().map(|w| format!("{w:016b}\n")).collect::<String>()).unwrap();
+        let binary_path = dir.join("words.bin");
+        std::fs::write(&binary_path, words.iter().flat_map(|w| w.to_le_bytes()).collect::<Vec<u8>>()).unwrap();
+        let hex_path = dir.join("words.hex");
+        std::fs::write(&hex_path, words.iter().map(|w| format!("{w:04X}\n")).collect::<String>()).unwrap();
+
+        let ascii = read_words(ascii_path.to_str().unwrap(), OutputFormat::Ascii);
+        let binary = read_words(binary_path.to_str().unwrap(), OutputFormat::Binary);
+        let hex = read_words(hex_path.to_str().unwrap(), OutputFormat::Hex);
+
+        assert_eq!(ascii, words);
+        assert_eq!(binary, words);
+        assert_eq!(hex, words);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn load_symbol_map_parses_sidecar_and_ignores_malformed_lines() {
+        let dir = std::env::temp_dir().join("rust_assembler_symbol_map_test");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("symbols.txt"), "LOOP\tlabel\t4\nfoo\tvariable\t16\nmalformed line\n").unwrap();
+
+        let symbol_map = load_symbol_map(dir.join("prog.hack").to_str().unwrap()).expect("sidecar should load");
+        assert_eq!(symbol_map.get(&4), Some(&"LOOP".to_string()));
+        assert_eq!(symbol_map.get(&16), Some(&"foo".to_string()));
+        assert_eq!(symbol_map.len(), 2);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn load_symbol_map_is_none_without_a_sidecar() {
+        let dir = std::env::temp_dir().join("rust_assembler_no_symbol_map_test");
+        std::fs::create_dir_all(&dir).unwrap();
+        assert!(load_symbol_map(dir.join("prog.hack").to_str().unwrap()).is_none());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
+
+/// Loads the optional `symbols.txt` sidecar (written as `symbol\tkind\taddress`
+/// per line) from the directory containing `hack_file_path`, if present.
+fn load_symbol_map(hack_file_path: &str) -> Option<HashMap<i16, String>> {
+    let directory = Path::new(hack_file_path).parent().unwrap_or_else(|| Path::new("."));
+    let file = File::open(directory.join("symbols.txt")).ok()?;
+    let reader = BufReader::new(file);
+    let mut address_to_symbol = HashMap::new();
+    for line in reader.lines() {
+        let line = line.expect("symbol map line parse fail");
+        let fields: Vec<&str> = line.split('\t').collect();
+        if fields.len() != 3 {
+            continue;
+        }
+        let address = fields[2].parse::<i16>().expect("malformed symbol map address");
+        address_to_symbol.insert(address, fields[0].to_string());
+    }
+    Some(address_to_symbol)
+}
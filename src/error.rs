@@ -0,0 +1,80 @@
+use std::fmt;
+
+/// A structured diagnostic carrying the source span (1-based line number and
+/// the original line text) of the offending input, so callers can report a
+/// caret pointing at the bad token instead of aborting with no context.
+#[derive(Debug)]
+pub struct AsmError {
+    pub line_number: usize,
+    pub line_orig: String,
+    pub kind: AsmErrorKind,
+}
+
+#[derive(Debug)]
+pub enum AsmErrorKind {
+    UnknownComp(String),
+    UnknownDest(String),
+    UnknownJump(String),
+    MalformedAInstruction(String),
+    DuplicateLabel(String),
+    UnterminatedLabel,
+    ConstantOutOfRange(i64),
+    MalformedMacroDef,
+    UnterminatedMacro(String),
+    MacroRecursionLimit(String),
+}
+
+impl AsmError {
+    pub fn new(line_number: usize, line_orig: &str, kind: AsmErrorKind) -> Self {
+        AsmError { line_number, line_orig: line_orig.to_string(), kind }
+    }
+
+    fn offending_token(&self) -> Option<&str> {
+        match &self.kind {
+            AsmErrorKind::UnknownComp(token)
+            | AsmErrorKind::UnknownDest(token)
+            | AsmErrorKind::UnknownJump(token)
+            | AsmErrorKind::MalformedAInstruction(token)
+            | AsmErrorKind::DuplicateLabel(token) => Some(token.as_str()),
+            AsmErrorKind::UnterminatedLabel
+            | AsmErrorKind::ConstantOutOfRange(_)
+            | AsmErrorKind::MalformedMacroDef
+            | AsmErrorKind::UnterminatedMacro(_)
+            | AsmErrorKind::MacroRecursionLimit(_) => None,
+        }
+    }
+
+    /// Prints the error with a caret pointing at the offending token within
+    /// the original source line, e.g.:
+    /// ```text
+    /// error: unknown comp mnemonic 'D+Z' (line 12)
+    ///   D=D+Z
+    ///     ^^^
+    /// ```
+    pub fn print(&self) {
+        eprintln!("error: {self} (line {})", self.line_number);
+        eprintln!("  {}", self.line_orig);
+        if let Some(token) = self.offending_token() {
+            if let Some(column) = self.line_orig.find(token) {
+                eprintln!("  {}{}", " ".repeat(column), "^".repeat(token.len().max(1)));
+            }
+        }
+    }
+}
+
+impl fmt::Display for AsmError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.kind {
+            AsmErrorKind::UnknownComp(token) => write!(f, "unknown comp mnemonic '{token}'"),
+            AsmErrorKind::UnknownDest(token) => write!(f, "unknown dest mnemonic '{token}'"),
+            AsmErrorKind::UnknownJump(token) => write!(f, "unknown jump mnemonic '{token}'"),
+            AsmErrorKind::MalformedAInstruction(token) => write!(f, "malformed A-instruction '@{token}'"),
+            AsmErrorKind::DuplicateLabel(symbol) => write!(f, "duplicate label '({symbol})'"),
+            AsmErrorKind::UnterminatedLabel => write!(f, "unterminated label, expected closing ')'"),
+            AsmErrorKind::ConstantOutOfRange(value) => write!(f, "constant {value} is out of range (max 32767)"),
+            AsmErrorKind::MalformedMacroDef => write!(f, "macro definition is missing a name"),
+            AsmErrorKind::UnterminatedMacro(name) => write!(f, "unterminated '.macro {name}' block, expected '.endmacro'"),
+            AsmErrorKind::MacroRecursionLimit(name) => write!(f, "macro '{name}' recursed past the max expansion depth"),
+        }
+    }
+}
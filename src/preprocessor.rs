@@ -0,0 +1,199 @@
+use std::collections::HashMap;
+
+use crate::error::{AsmError, AsmErrorKind};
+
+/// Maximum number of nested expansions for a single macro name before we
+/// assume it is expanding itself and bail out instead of looping forever.
+const MAX_EXPANSION_DEPTH: usize = 64;
+
+struct MacroDef {
+    params: Vec<String>,
+    body: Vec<(usize, String)>,
+}
+
+type MacroTable = HashMap<String, MacroDef>;
+
+/// Runs the two-scan macro preprocessor over raw (comment-stripped, trimmed,
+/// non-empty) source lines, each tagged with its 1-based line number in the
+/// original file, and returns the fully expanded `(line_number, line)` stream
+/// that can be fed to `parse_line` unchanged. Expanded lines carry the line
+/// number of the macro body line they came from, since that is the most
+/// useful span to report for a bug introduced by a macro expansion.
+pub fn expand_macros(lines: Vec<(usize, String)>) -> Result<Vec<(usize, String)>, Vec<AsmError>> {
+    let (macros, body_lines, mut errors) = collect_macros(lines);
+    let mut expansion_stack = Vec::new();
+    let mut expanded = Vec::new();
+    expand_lines(&body_lines, &macros, &mut expansion_stack, &mut expanded, &mut errors);
+    if errors.is_empty() {
+        Ok(expanded)
+    } else {
+        Err(errors)
+    }
+}
+
+/// First scan: pulls out `.macro NAME p1 p2 ... .endmacro` blocks into a
+/// `MacroTable` and strips them from the line stream.
+fn collect_macros(lines: Vec<(usize, String)>) -> (MacroTable, Vec<(usize, String)>, Vec<AsmError>) {
+    let mut macros = MacroTable::new();
+    let mut remaining = Vec::new();
+    let mut errors = Vec::new();
+    let mut lines = lines.into_iter();
+    while let Some((line_number, line)) = lines.next() {
+        if line.starts_with(".macro") {
+            let mut tokens = line.split_whitespace();
+            tokens.next(); // ".macro"
+            let name = match tokens.next() {
+                Some(name) => name.to_string(),
+                None => {
+                    errors.push(AsmError::new(line_number, &line, AsmErrorKind::MalformedMacroDef));
+                    continue;
+                }
+            };
+            let params: Vec<String> = tokens.map(|s| s.to_string()).collect();
+            let mut body = Vec::new();
+            let mut terminated = false;
+            for (body_line_number, body_line) in lines.by_ref() {
+                if body_line == ".endmacro" {
+                    terminated = true;
+                    break;
+                }
+                body.push((body_line_number, body_line));
+            }
+            if !terminated {
+                errors.push(AsmError::new(line_number, &line, AsmErrorKind::UnterminatedMacro(name)));
+                continue;
+            }
+            macros.insert(name, MacroDef { params, body });
+        } else {
+            remaining.push((line_number, line));
+        }
+    }
+    (macros, remaining, errors)
+}
+
+/// Second scan: substitutes each macro call with its body, re-scanning the
+/// result so macros that call other macros expand fully. Errors (expansion
+/// depth overruns) are collected rather than aborting, so one bad macro call
+/// doesn't hide diagnostics about the rest of the file.
+fn expand_lines(
+    lines: &[(usize, String)],
+    macros: &MacroTable,
+    expansion_stack: &mut Vec<String>,
+    expanded: &mut Vec<(usize, String)>,
+    errors: &mut Vec<AsmError>,
+) {
+    for (line_number, line) in lines {
+        let mut tokens = line.split_whitespace();
+        let name = tokens.next().unwrap_or("");
+        match macros.get(name) {
+            Some(macro_def) => {
+                let depth = expansion_stack.iter().filter(|called| called.as_str() == name).count();
+                if depth >= MAX_EXPANSION_DEPTH {
+                    errors.push(AsmError::new(*line_number, line, AsmErrorKind::MacroRecursionLimit(name.to_string())));
+                    continue;
+                }
+                let args: Vec<&str> = tokens.collect();
+                let substituted: Vec<(usize, String)> = macro_def
+                    .body
+                    .iter()
+                    .map(|(body_line_number, body_line)| {
+                        (*body_line_number, substitute_line(body_line, &macro_def.params, &args))
+                    })
+                    .collect();
+                expansion_stack.push(name.to_string());
+                expand_lines(&substituted, macros, expansion_stack, expanded, errors);
+                expansion_stack.pop();
+            }
+            None => expanded.push((*line_number, line.clone())),
+        }
+    }
+}
+
+/// Substitutes every whole-token occurrence of a param name in `line` with
+/// the matching call-site argument, in a single left-to-right pass over the
+/// original text. Matching on whole tokens (not substrings) and building the
+/// output from the original body text in one pass (rather than chaining
+/// sequential `str::replace` calls) avoids two failure modes: a substituted
+/// argument being re-matched by a later param's replacement, and a param
+/// name that is a substring of another identifier (e.g. `R` inside `R1`)
+/// being replaced by mistake.
+fn substitute_line(line: &str, params: &[String], args: &[&str]) -> String {
+    let chars: Vec<char> = line.chars().collect();
+    let mut output = String::with_capacity(line.len());
+    let mut i = 0;
+    while i < chars.len() {
+        if is_word_char(chars[i]) {
+            let start = i;
+            while i < chars.len() && is_word_char(chars[i]) {
+                i += 1;
+            }
+            let token: String = chars[start..i].iter().collect();
+            match params.iter().position(|param| *param == token) {
+                Some(param_idx) => output.push_str(args.get(param_idx).copied().unwrap_or("")),
+                None => output.push_str(&token),
+            }
+        } else {
+            output.push(chars[i]);
+            i += 1;
+        }
+    }
+    output
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(raw: &[&str]) -> Vec<(usize, String)> {
+        raw.iter().enumerate().map(|(idx, line)| (idx + 1, line.to_string())).collect()
+    }
+
+    fn line_texts(expanded: &[(usize, String)]) -> Vec<&str> {
+        expanded.iter().map(|(_, line)| line.as_str()).collect()
+    }
+
+    #[test]
+    fn substitutes_params_simultaneously_without_cascading() {
+        // SWAP p1 p2 called as `SWAP p2 R1` must not let the literal `p2`
+        // substituted for p1 get re-matched by the p2->R1 substitution.
+        let params = vec!["p1".to_string(), "p2".to_string()];
+        let args = ["p2", "R1"];
+        assert_eq!(substitute_line("@p1", &params, &args), "@p2");
+        assert_eq!(substitute_line("@p2", &params, &args), "@R1");
+    }
+
+    #[test]
+    fn substitutes_only_whole_token_matches() {
+        // A param named `R` must not match inside the unrelated token `R1`.
+        let params = vec!["R".to_string()];
+        let args = ["5"];
+        assert_eq!(substitute_line("@R1", &params, &args), "@R1");
+        assert_eq!(substitute_line("@R", &params, &args), "@5");
+    }
+
+    #[test]
+    fn expands_simple_macro_call() {
+        let source = lines(&[".macro INC p", "@p", "M=M+1", ".endmacro", "INC 5"]);
+        let expanded = expand_macros(source).expect("expansion should succeed");
+        assert_eq!(line_texts(&expanded), vec!["@5", "M=M+1"]);
+    }
+
+    #[test]
+    fn reports_unterminated_macro_block() {
+        let source = lines(&[".macro INC p", "@p"]);
+        let errors = expand_macros(source).expect_err("missing .endmacro should be an error");
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(errors[0].kind, AsmErrorKind::UnterminatedMacro(_)));
+    }
+
+    #[test]
+    fn reports_self_referential_macro_instead_of_panicking() {
+        let source = lines(&[".macro LOOP p", "LOOP p", ".endmacro", "LOOP 1"]);
+        let errors = expand_macros(source).expect_err("self-recursive macro should be an error");
+        assert!(errors.iter().any(|error| matches!(error.kind, AsmErrorKind::MacroRecursionLimit(_))));
+    }
+}